@@ -4,7 +4,9 @@ use kdapp::{
     pki::PubKey,
 };
 use log::info;
-use rand::{seq::SliceRandom, thread_rng};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::collections::VecDeque;
 
 // --- Core Blackjack Game Structures ---
 
@@ -86,6 +88,8 @@ impl std::fmt::Display for BlackjackError {
 impl std::error::Error for BlackjackError {}
 
 
+// TODO(kdapp#chunk0-4): EpisodeMessage wire versioning is unimplemented; it needs
+// kdapp::engine, which this tree doesn't vendor.
 #[derive(Clone, Copy, Debug, BorshSerialize, BorshDeserialize)]
 pub enum BlackjackCommand {
     Deal,
@@ -135,6 +139,15 @@ pub enum BlackjackRollback {
     Stand,
 }
 
+// Full pre-execute state checkpoint, since every field here is cheap to clone.
+#[derive(Clone, Debug)]
+struct BlackjackSnapshot {
+    deck: Vec<Card>,
+    player_hand: Hand,
+    dealer_hand: Hand,
+    status: BlackjackGameStatus,
+}
+
 #[derive(Clone, Debug)]
 pub struct BlackjackEpisode {
     pub players: Vec<PubKey>, // [0] is player, [1] is dealer
@@ -143,6 +156,37 @@ pub struct BlackjackEpisode {
     dealer_hand: Hand,
     status: BlackjackGameStatus,
     timestamp: u64,
+    history: VecDeque<BlackjackSnapshot>,
+}
+
+impl BlackjackEpisode {
+    const MAX_SNAPSHOTS: usize = 16;
+
+    fn snapshot(&self) -> BlackjackSnapshot {
+        BlackjackSnapshot {
+            deck: self.deck.clone(),
+            player_hand: self.player_hand.clone(),
+            dealer_hand: self.dealer_hand.clone(),
+            status: self.status.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: BlackjackSnapshot) {
+        self.deck = snapshot.deck;
+        self.player_hand = snapshot.player_hand;
+        self.dealer_hand = snapshot.dealer_hand;
+        self.status = snapshot.status;
+    }
+
+    // Call only once a command is validated and about to mutate state, so every pushed
+    // snapshot pairs with a future rollback.
+    fn push_snapshot(&mut self) {
+        if self.history.len() == Self::MAX_SNAPSHOTS {
+            self.history.pop_front();
+            log::warn!("[Blackjack] snapshot ring full, evicting oldest checkpoint; rollback beyond {} commands deep will fail", Self::MAX_SNAPSHOTS);
+        }
+        self.history.push_back(self.snapshot());
+    }
 }
 
 impl Episode for BlackjackEpisode {
@@ -159,6 +203,7 @@ impl Episode for BlackjackEpisode {
             dealer_hand: Hand::default(),
             status: BlackjackGameStatus::Pending,
             timestamp: metadata.accepting_time,
+            history: VecDeque::new(),
         }
     }
 
@@ -175,8 +220,9 @@ impl Episode for BlackjackEpisode {
                 if !matches!(self.status, BlackjackGameStatus::Pending) {
                     return Err(EpisodeError::InvalidCommand(BlackjackError::InvalidCommand));
                 }
+                self.push_snapshot();
                 self.deck = Self::new_deck();
-                self.deck.shuffle(&mut thread_rng());
+                self.deck.shuffle(&mut Self::deal_rng(&self.players, self.timestamp, metadata));
                 self.player_hand = Hand::default();
                 self.dealer_hand = Hand::default();
 
@@ -192,6 +238,7 @@ impl Episode for BlackjackEpisode {
                 if !matches!(self.status, BlackjackGameStatus::PlayerTurn) || player != self.players[0] {
                     return Err(EpisodeError::InvalidCommand(BlackjackError::NotPlayersTurn));
                 }
+                self.push_snapshot();
                 self.player_hand.add_card(self.deck.pop().unwrap());
                 if self.player_hand.value() > 21 {
                     self.status = BlackjackGameStatus::Bust(self.players[0]);
@@ -202,6 +249,7 @@ impl Episode for BlackjackEpisode {
                  if !matches!(self.status, BlackjackGameStatus::PlayerTurn) || player != self.players[0] {
                     return Err(EpisodeError::InvalidCommand(BlackjackError::NotPlayersTurn));
                 }
+                self.push_snapshot();
                 self.status = BlackjackGameStatus::DealerTurn;
                 self.play_dealer_turn();
                 Ok(BlackjackRollback::Stand)
@@ -210,13 +258,32 @@ impl Episode for BlackjackEpisode {
     }
 
     fn rollback(&mut self, _rollback: Self::CommandRollback) -> bool {
-        // For this simple version, we won't implement a full state rollback.
-        // A real implementation would need to restore the deck and hands precisely.
-        true
+        match self.history.pop_back() {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
     }
 }
 
 impl BlackjackEpisode {
+    // Seeded from consensus data (participants, accepting time) instead of `thread_rng()`
+    // so every node shuffles identically. Safe only because `Deal` fires at most once per
+    // episode; reusing this recipe for a command that can repeat needs tx_id/command_index
+    // folded in too. Note: the seed is itself public on-chain data, so the deck is
+    // predictable to any observer as soon as the `Deal` tx lands — this is not a fix for that.
+    fn deal_rng(players: &[PubKey], timestamp: u64, metadata: &PayloadMetadata) -> ChaCha20Rng {
+        let mut hasher = blake3::Hasher::new();
+        for player in players {
+            hasher.update(&player.0.serialize());
+        }
+        hasher.update(&timestamp.to_le_bytes());
+        hasher.update(&metadata.accepting_time.to_le_bytes());
+        ChaCha20Rng::from_seed(*hasher.finalize().as_bytes())
+    }
+
     fn new_deck() -> Vec<Card> {
         let suits = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
         let ranks = [
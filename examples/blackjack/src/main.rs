@@ -7,7 +7,8 @@ use kaspa_consensus_core::{
 };
 use kaspa_wrpc_client::prelude::*;
 use log::*;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use secp256k1::{Keypair, PublicKey, SecretKey};
 use std::{
     str::FromStr,
@@ -131,17 +132,59 @@ async fn main() {
     });
 
     // Run the kaspad listener
-    proxy::run_listener(kaspad, std::iter::once((PREFIX, (PATTERN, sender))).collect(), exit_signal_receiver).await;
+    proxy::run_listener(kaspad, std::iter::once((PREFIX, (derive_pattern(PREFIX), sender))).collect(), exit_signal_receiver).await;
 
     engine_task.await.unwrap();
     player_task.await.unwrap();
 }
 
-// TODO: derive pattern from prefix (using prefix as a random seed for composing the pattern)
-const PATTERN: PatternType = [(7, 0), (32, 1), (45, 0), (99, 1), (113, 0), (126, 1), (189, 0), (200, 1), (211, 0), (250, 1)];
 const PREFIX: PrefixType = 858598618;
 const FEE: u64 = 5000;
 
+// Ideally this lives in `kdapp::generator` as `generator::derive_pattern`, so every app
+// gets a collision-resistant matching pattern straight from its prefix instead of
+// shipping a hand-picked constant. Until that lands upstream (the vendored snapshot in
+// this repo doesn't carry `generator.rs`), derive it here with the same recipe: seed a
+// ChaCha20Rng from the prefix bytes and pick 10 distinct, sorted bit positions across the
+// transaction id space.
+fn derive_pattern(prefix: PrefixType) -> PatternType {
+    let mut seed = [0u8; 32];
+    seed[..4].copy_from_slice(&prefix.to_le_bytes());
+    let mut rng = ChaCha20Rng::from_seed(seed);
+
+    let mut indices = std::collections::BTreeSet::new();
+    while indices.len() < 10 {
+        indices.insert(rng.gen::<u8>());
+    }
+
+    let mut pattern = [(0u8, 0u8); 10];
+    for (slot, bit_index) in pattern.iter_mut().zip(indices) {
+        *slot = (bit_index, rng.gen_range(0..=1u8));
+    }
+    pattern
+}
+
+// PARTIAL (kdapp#chunk0-5): deterministic id derivation only. Collision rejection still
+// needs an engine::Engine check plus an on_collision/on_rejected EpisodeEventHandler hook,
+// neither of which exist in this tree, so a colliding NewEpisode still silently overwrites.
+fn derive_episode_id(participants: &[PubKey], utxo: &(TransactionOutpoint, UtxoEntry)) -> EpisodeId {
+    let mut sorted = participants.to_vec();
+    sorted.sort_by_key(|pk| pk.0.serialize());
+
+    let mut hasher = blake3::Hasher::new();
+    for pk in &sorted {
+        hasher.update(&pk.0.serialize());
+    }
+    // Hash the outpoint's own fields, not its `Debug` form — `Debug` isn't a stable API
+    // and could format differently across dependency versions, producing different ids
+    // for nodes on different builds.
+    hasher.update(utxo.0.transaction_id.as_bytes());
+    hasher.update(&utxo.0.index.to_le_bytes());
+
+    let hash = hasher.finalize();
+    EpisodeId::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap())
+}
+
 struct BlackjackHandler {
     sender: UnboundedSender<(EpisodeId, BlackjackState)>,
     player: PubKey, // The local player pubkey
@@ -186,13 +229,11 @@ async fn play_blackjack(
     let entry = if opponent_pk.is_some() { entries.first().cloned() } else { entries.last().cloned() };
     let mut utxo = entry.map(|entry| (TransactionOutpoint::from(entry.outpoint), UtxoEntry::from(entry.utxo_entry))).unwrap();
 
-    let generator = generator::TransactionGenerator::new(kaspa_signer, PATTERN, PREFIX);
+    let generator = generator::TransactionGenerator::new(kaspa_signer, derive_pattern(PREFIX), PREFIX);
 
     // When opponent pk is passed, we are expected to initiate the game
     if let Some(opponent_pk) = opponent_pk {
-        // Use a simple rand method
-        // TODO: a complete implementation must handle collisions
-        let episode_id = rand::thread_rng().gen();
+        let episode_id = derive_episode_id(&[player_pk, opponent_pk], &utxo);
         let new_episode = EpisodeMessage::<BlackjackEpisode>::NewEpisode { episode_id, participants: vec![player_pk, opponent_pk] };
         let tx = generator.build_command_transaction(utxo, &kaspa_addr, &new_episode, FEE);
         info!("Submitting initialize command: {}", tx.id());